@@ -1,3 +1,6 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
 use std::time;
 use std::{fmt, iter::DoubleEndedIterator, mem, vec::Vec};
 
@@ -43,6 +46,30 @@ impl<T> HistoryBuffer<T> {
         self.buffer.len()
     }
 
+    /// Change the capacity of a live buffer.
+    ///
+    /// When shrinking, the `new_max` most-recent elements are retained and the
+    /// oldest are dropped; when growing, all existing elements stay and the
+    /// buffer simply gains room before it wraps again. Chronological order is
+    /// preserved in both cases.
+    pub fn set_max_len(&mut self, new_max: usize) {
+        // Re-linearize into chronological order before re-keying to the new
+        // capacity, since the physical layout is tied to `write_index`.
+        let was_full = self.is_full();
+        let mut data = mem::take(&mut self.buffer);
+        if was_full {
+            data.rotate_left(self.write_index);
+        }
+        if data.len() > new_max {
+            let excess = data.len() - new_max;
+            data.drain(..excess);
+        }
+        data.reserve(new_max.saturating_sub(data.len()));
+        self.write_index = if new_max == 0 { 0 } else { data.len() % new_max };
+        self.buffer = data;
+        self.max_size = new_max;
+    }
+
     /// Clear all values in the buffer.
     pub fn clear(&mut self) {
         self.write_index = 0;
@@ -78,6 +105,101 @@ impl<T> HistoryBuffer<T> {
         r
     }
 
+    /// Write a new value, suppressing it if it equals the most recent one.
+    ///
+    /// When `val` matches [`most_recent`](Self::most_recent) the buffer is left
+    /// untouched — `write_index` does not advance and nothing is appended —
+    /// though `last_data_at` is still refreshed; the rejected `val` is handed
+    /// back to the caller. Otherwise this behaves like [`write`](Self::write)
+    /// and returns any overwritten element. Useful for sampling a signal that
+    /// frequently repeats when the history should track distinct changes.
+    pub fn write_dedup(&mut self, val: T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        if self.most_recent() == Some(&val) {
+            self.last_data_at = time::Instant::now();
+            Some(val)
+        } else {
+            self.write(val)
+        }
+    }
+
+    /// Write every element yielded by `iter` into the buffer.
+    ///
+    /// When the incoming batch is at least `max_size` elements, only the final
+    /// `max_size` items are materialized and the earlier ones are skipped,
+    /// turning a long stream into an O(`max_size`) operation at the tail
+    /// instead of overwriting the same slots repeatedly. `last_data_at` is
+    /// refreshed once, after the whole batch has been consumed.
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // Keep only the last `max_size` items in a small ring starting at
+        // `head`; everything before them would be overwritten anyway.
+        let mut tail: Vec<T> = Vec::new();
+        let mut head = 0;
+        let mut count: usize = 0;
+        for item in iter {
+            if tail.len() < self.max_size {
+                tail.push(item);
+            } else {
+                tail[head] = item;
+                head = (head + 1) % self.max_size;
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            return;
+        }
+
+        if count >= self.max_size {
+            // `tail` holds exactly the final `max_size` items as a ring
+            // starting at `head`; re-linearize into chronological order.
+            let mut buffer = Vec::with_capacity(self.max_size);
+            buffer.extend(tail.drain(head..));
+            buffer.append(&mut tail);
+            self.buffer = buffer;
+            self.write_index = 0;
+        } else {
+            // Fewer than `max_size` items: append them in order, wrapping only
+            // if the buffer was already partially filled.
+            for item in tail {
+                if self.is_full() {
+                    self.buffer[self.write_index] = item;
+                } else {
+                    self.buffer.push(item);
+                }
+                self.write_index = (self.write_index.wrapping_add(1)) % self.max_size;
+            }
+        }
+
+        self.last_data_at = time::Instant::now();
+    }
+
+    /// Clone every element of `other` into the buffer, mirroring [`extend`].
+    ///
+    /// Only the final `max_size` elements are cloned when `other` is at least
+    /// that long, so the discarded prefix never pays for a clone.
+    ///
+    /// [`extend`]: Self::extend
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        if other.len() >= self.max_size {
+            self.buffer = other[other.len() - self.max_size..].to_vec();
+            self.write_index = 0;
+            if !other.is_empty() {
+                self.last_data_at = time::Instant::now();
+            }
+        } else {
+            self.extend(other.iter().cloned());
+        }
+    }
+
     /// How long was it since the last measurement.
     pub fn duration_since_last_measurement(&self) -> Option<time::Duration> {
         if !self.is_empty() {
@@ -100,6 +222,187 @@ impl<T> HistoryBuffer<T> {
             .iter()
             .chain(self.buffer[..write_index].iter())
     }
+
+    /// Translate a logical index into an offset into the physical `buffer`.
+    ///
+    /// Positive indices count forward from the oldest retained element (`0`),
+    /// negative indices count back from the most recent write (`-1`). Returns
+    /// `None` when the index falls outside the currently-filled region.
+    fn physical_index(&self, index: isize) -> Option<usize> {
+        let len = self.buffer.len();
+        let logical = if index < 0 {
+            len.checked_sub(index.unsigned_abs())?
+        } else {
+            let index = index as usize;
+            if index >= len {
+                return None;
+            }
+            index
+        };
+        // The oldest retained element lives at `write_index` once the buffer
+        // has wrapped, and at `0` while it is still filling.
+        let start = if self.is_full() { self.write_index } else { 0 };
+        Some((start + logical) % self.max_size)
+    }
+
+    /// Get the value `index` samples into the buffer.
+    ///
+    /// Index `-1` is the most recent write, `-2` the one before it, while
+    /// positive indices count forward from the oldest retained element.
+    /// Returns `None` when the index is outside the filled region.
+    pub fn get(&self, index: isize) -> Option<&T> {
+        self.physical_index(index).map(|i| &self.buffer[i])
+    }
+
+    /// Mutable variant of [`get`](Self::get).
+    pub fn get_mut(&mut self, index: isize) -> Option<&mut T> {
+        self.physical_index(index).map(move |i| &mut self.buffer[i])
+    }
+
+    /// Index the underlying physical `buffer` slot directly, ignoring the
+    /// logical ordering imposed by `write_index`.
+    pub fn get_absolute(&self, index: usize) -> Option<&T> {
+        self.buffer.get(index)
+    }
+
+    /// Write the buffer to `path`, one element per line in chronological order.
+    ///
+    /// Keeps a rolling on-disk log of recent values that survives restarts;
+    /// pair with [`load_from`](Self::load_from) to read it back.
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()>
+    where
+        T: fmt::Display,
+    {
+        let mut file = BufWriter::new(File::create(path)?);
+        for item in self.all() {
+            writeln!(file, "{}", item)?;
+        }
+        file.flush()
+    }
+
+    /// Read up to `max_size` most-recent entries back from a file written by
+    /// [`save_to`](Self::save_to), reconstructing the buffer.
+    pub fn load_from<P: AsRef<Path>>(path: P, max_size: usize) -> io::Result<Self>
+    where
+        T: core::str::FromStr,
+    {
+        let reader = BufReader::new(File::open(path)?);
+        let mut buffer = HistoryBuffer::new(max_size);
+        for line in reader.lines() {
+            let line = line?;
+            let val = line.parse::<T>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "failed to parse history entry")
+            })?;
+            buffer.write(val);
+        }
+        Ok(buffer)
+    }
+
+    /// Sum of the current contents. Order is irrelevant, so this reduces over
+    /// [`all_unsorted`](Self::all_unsorted).
+    pub fn sum(&self) -> T
+    where
+        T: Copy + core::iter::Sum,
+    {
+        self.all_unsorted().iter().copied().sum()
+    }
+
+    /// Arithmetic mean of the current contents, or `None` when empty.
+    pub fn mean(&self) -> Option<f64>
+    where
+        T: Copy + Into<f64>,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            let sum: f64 = self.all_unsorted().iter().map(|&x| x.into()).sum();
+            Some(sum / self.buffer.len() as f64)
+        }
+    }
+
+    /// Smallest element currently in the buffer, or `None` when empty.
+    pub fn min(&self) -> Option<T>
+    where
+        T: Copy + PartialOrd,
+    {
+        self.all_unsorted()
+            .iter()
+            .copied()
+            .reduce(|a, b| if b < a { b } else { a })
+    }
+
+    /// Largest element currently in the buffer, or `None` when empty.
+    pub fn max(&self) -> Option<T>
+    where
+        T: Copy + PartialOrd,
+    {
+        self.all_unsorted()
+            .iter()
+            .copied()
+            .reduce(|a, b| if b > a { b } else { a })
+    }
+
+    /// Write `val` while maintaining a running sum in O(1).
+    ///
+    /// Rather than re-summing [`all_unsorted`](Self::all_unsorted) on every
+    /// sample, this folds `val` into `prev_sum` and subtracts the element
+    /// evicted by the underlying [`write`](Self::write), returning the updated
+    /// sum; a rolling mean is then `sum / len()`.
+    pub fn write_sum(&mut self, val: T, prev_sum: T) -> T
+    where
+        T: Copy + core::ops::Add<Output = T> + core::ops::Sub<Output = T>,
+    {
+        let evicted = self.write(val);
+        let sum = prev_sum + val;
+        match evicted {
+            Some(old) => sum - old,
+            None => sum,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for HistoryBuffer<T>
+where
+    T: serde::Serialize,
+{
+    /// Serializes the elements in chronological order, oldest first.
+    ///
+    /// `last_data_at` is not part of the representation; see the `Deserialize`
+    /// impl for how it is restored.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.all())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for HistoryBuffer<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    /// Rebuilds a buffer whose `max_size` equals the number of deserialized
+    /// elements, with `write_index` positioned so the next `write` overwrites
+    /// the oldest element. `last_data_at` cannot be serialized and is reset to
+    /// `Instant::now()`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let buffer = Vec::<T>::deserialize(deserializer)?;
+        let max_size = buffer.len();
+        Ok(HistoryBuffer {
+            max_size,
+            // The elements arrive oldest first and fill the buffer, so the
+            // oldest sits at index 0; with `write_index` at 0 the next write
+            // overwrites it.
+            write_index: 0,
+            buffer,
+            last_data_at: time::Instant::now(),
+        })
+    }
 }
 
 impl<T> fmt::Debug for HistoryBuffer<T>